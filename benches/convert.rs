@@ -0,0 +1,30 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+use temperature_converter::{Scale, Temperature};
+
+const SAMPLES: usize = 100_000;
+
+// Converts the same spread of values, from absolute zero up, for every source/target pair.
+fn convert(c: &mut Criterion) {
+    let mut group = c.benchmark_group("convert");
+    group.throughput(Throughput::Elements(SAMPLES as u64));
+
+    for from in Scale::ALL {
+        for to in Scale::ALL.into_iter().filter(|to| *to != from) {
+            let temperatures: Vec<Temperature> = (0..SAMPLES)
+                .map(|index| Temperature::new(from.absolute_zero() + index as f64 * 0.01, from, to))
+                .collect();
+
+            group.bench_function(format!("{}{}", from, to), |b| {
+                b.iter(|| temperatures.iter().map(|temp| black_box(temp).unchecked_convert()).sum::<f64>())
+            });
+            group.bench_function(format!("{}{} precise", from, to), |b| {
+                b.iter(|| temperatures.iter().map(|temp| black_box(temp).precise_convert()).sum::<f64>())
+            });
+        }
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, convert);
+criterion_main!(benches);
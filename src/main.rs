@@ -1,170 +1,984 @@
-use std::fmt::{Debug, Display, Formatter};
+use std::ffi::OsStr;
+use std::fs::File;
+use std::io::{BufWriter, IsTerminal, Write};
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
 
-#[derive(Debug, Clone)]
-enum TemperatureErrorKind {
-    NotNumeric,
-    ScaleUnknown,
-    Inconvertible,
+use temperature_converter::{
+    conversion_formula, format_number, parse_scale_pair, water_boiling_point, water_freezing_point, ParseTemperatureError, Scale, Temperature,
+};
+
+#[derive(Default, Clone, Copy, PartialEq)]
+enum OutputFormat {
+    #[default]
+    Arrow,
+    RowAll,
+    Numbers,
+    Logfmt,
+    Columns,
+    Minimal,
+}
+
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(format: &str) -> Result<Self, Self::Err> {
+        match format {
+            "arrow" => Ok(OutputFormat::Arrow),
+            "row-all" => Ok(OutputFormat::RowAll),
+            "numbers" => Ok(OutputFormat::Numbers),
+            "logfmt" => Ok(OutputFormat::Logfmt),
+            "columns" => Ok(OutputFormat::Columns),
+            "minimal" => Ok(OutputFormat::Minimal),
+            _ => Err(format!("unknown format {}, expected arrow, row-all, numbers, logfmt, columns or minimal", format))
+        }
+    }
 }
 
-#[derive(Debug, Clone)]
-pub struct ParseTemperatureError {
-    kind: TemperatureErrorKind,
+#[derive(Default, Clone, Copy, PartialEq)]
+enum DecimalSeparator {
+    #[default]
+    Point,
+    Comma,
 }
 
-impl ParseTemperatureError {
-    #[doc(hidden)]
-    pub fn __description(&self) -> &str {
-        match self.kind {
-            TemperatureErrorKind::NotNumeric => "not a numeric value",
-            TemperatureErrorKind::ScaleUnknown => "scale unknown",
-            TemperatureErrorKind::Inconvertible => "string is empty"
+impl FromStr for DecimalSeparator {
+    type Err = String;
+
+    fn from_str(locale: &str) -> Result<Self, Self::Err> {
+        let language = locale.split(['_', '-', '.']).next().unwrap_or_default().to_lowercase();
+
+        match language.as_str() {
+            "c" | "en" | "ja" | "ko" | "zh" => Ok(DecimalSeparator::Point),
+            "de" | "es" | "fr" | "it" | "nl" | "pl" | "pt" | "ru" => Ok(DecimalSeparator::Comma),
+            _ => Err(format!("unsupported locale {}", locale))
         }
     }
 }
 
-impl std::fmt::Display for ParseTemperatureError {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        Debug::fmt(self.__description(), f)
+#[derive(Clone, Copy, PartialEq)]
+enum SortOrder {
+    Ascending,
+    Descending,
+}
+
+impl FromStr for SortOrder {
+    type Err = String;
+
+    fn from_str(order: &str) -> Result<Self, Self::Err> {
+        match order {
+            "asc" => Ok(SortOrder::Ascending),
+            "desc" => Ok(SortOrder::Descending),
+            _ => Err(format!("unknown sort order {}, expected asc or desc", order))
+        }
     }
 }
 
-impl std::error::Error for ParseTemperatureError {}
+#[derive(Default, Clone, Copy, PartialEq)]
+enum ColorChoice {
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+impl FromStr for ColorChoice {
+    type Err = String;
 
-#[derive(Copy, Clone)]
-enum Scale {
-    Celsius,
-    Fahrenheit,
-    Kelvin,
+    fn from_str(choice: &str) -> Result<Self, Self::Err> {
+        match choice {
+            "auto" => Ok(ColorChoice::Auto),
+            "always" => Ok(ColorChoice::Always),
+            "never" => Ok(ColorChoice::Never),
+            _ => Err(format!("unknown color choice {}, expected auto, always or never", choice))
+        }
+    }
 }
 
-impl Display for Scale {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        let output = match self {
-            Scale::Celsius => 'C',
-            Scale::Fahrenheit => 'F',
-            Scale::Kelvin => 'K'
-        };
+const COLD_BELOW_C: f64 = 10.0;
+const HOT_FROM_C: f64 = 30.0;
 
-        write!(f, "{}", output)
+// ANSI color for a temperature given in Celsius: blue when cold, red when hot, none in between.
+fn color_for(celsius: f64) -> Option<&'static str> {
+    if celsius < COLD_BELOW_C {
+        Some("\x1b[34m")
+    } else if celsius >= HOT_FROM_C {
+        Some("\x1b[31m")
+    } else {
+        None
     }
 }
 
-struct Temperature {
-    value: f64,
-    scale: Scale,
-    convert_to: Scale,
+// Calls `report` with the running count every `interval` ticks.
+struct Progress<F: FnMut(usize)> {
+    interval: usize,
+    count: usize,
+    report: F,
 }
 
-impl Temperature {
-    const K: f64 = 273.15;
+impl<F: FnMut(usize)> Progress<F> {
+    fn new(interval: usize, report: F) -> Progress<F> {
+        Progress { interval, count: 0, report }
+    }
 
-    fn f_c(f: f64) -> f64 {
-        (f - 32.0) * 5.0 / 9.0
+    fn tick(&mut self) {
+        self.count += 1;
+        if self.count.is_multiple_of(self.interval) {
+            (self.report)(self.count);
+        }
     }
+}
+
+// The `--progress` counter shared by every input path, if one was requested.
+fn progress_for(options: &Options) -> Option<Progress<fn(usize)>> {
+    let report: fn(usize) = |count| eprintln!("processed {} inputs", count);
+    options.progress.map(|interval| Progress::new(interval, report))
+}
+
+#[derive(Default)]
+struct Options {
+    json_input: bool,
+    tsv_input: bool,
+    log_scale: Option<Scale>,
+    show_plus: bool,
+    show_delta: bool,
+    describe: bool,
+    decimal_separator: DecimalSeparator,
+    water_points: Option<Scale>,
+    sort: Option<SortOrder>,
+    progress: Option<usize>,
+    to: Option<Scale>,
+    default_target: Option<Scale>,
+    auto: bool,
+    guess: bool,
+    limit: Option<usize>,
+    output: Option<String>,
+    color: ColorChoice,
+    dedup: bool,
+    format: OutputFormat,
+    delimiter: Option<String>,
+    precision: Option<usize>,
+    match_precision: bool,
+    dump: bool,
+    validate: bool,
+    compact: bool,
+    count_only: bool,
+    with_formula: bool,
+    both: bool,
+}
+
+fn parse_scale_arg(scale: &str) -> Result<Scale, String> {
+    Scale::from_str(scale).map_err(|err| format!("{}, {}", scale, err))
+}
+
+fn parse_precision(precision: &str) -> Result<usize, String> {
+    usize::from_str(precision).map_err(|_| format!("invalid precision {}", precision))
+}
+
+// Every flag that picks the output format, and so overrides `TEMPCONV_FORMAT`.
+const FORMAT_FLAGS: [&str; 6] = ["--format", "--row-all", "--numbers-only", "--logfmt", "--columns", "--minimal"];
 
-    fn c_f(c: f64) -> f64 {
-        (c * 9.0 / 5.0) + 32.0
+// `TEMPCONV_PRECISION` and `TEMPCONV_FORMAT` provide the defaults that `--precision` and
+// `--format` override, so a bad value is only reported when no flag replaced it.
+fn apply_env_defaults(options: &mut Options, format_flag: bool) -> Result<(), String> {
+    if options.precision.is_none() {
+        if let Ok(precision) = std::env::var("TEMPCONV_PRECISION") {
+            options.precision = Some(parse_precision(&precision).map_err(|err| format!("TEMPCONV_PRECISION: {}", err))?);
+        }
     }
 
-    fn c_k(c: f64, k: f64) -> f64 {
-        c + k
+    if !format_flag {
+        if let Ok(format) = std::env::var("TEMPCONV_FORMAT") {
+            options.format = OutputFormat::from_str(&format).map_err(|err| format!("TEMPCONV_FORMAT: {}", err))?;
+        }
     }
 
-    pub fn convert(&self) -> f64 {
-        return match (self.scale, self.convert_to) {
-            (Scale::Celsius, Scale::Kelvin) => Temperature::c_k(self.value, Temperature::K),
-            (Scale::Celsius, Scale::Fahrenheit) => Temperature::c_f(self.value),
-            (Scale::Fahrenheit, Scale::Celsius) => Temperature::f_c(self.value),
-            (Scale::Fahrenheit, Scale::Kelvin) => Temperature::c_k(Temperature::f_c(self.value), Temperature::K),
-            (Scale::Kelvin, Scale::Celsius) => Temperature::c_k(self.value, -Temperature::K),
-            (Scale::Kelvin, Scale::Fahrenheit) => Temperature::c_f(Temperature::c_k(self.value, -Temperature::K)),
-            _ => self.value
-        };
+    Ok(())
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<(Options, Vec<String>), String> {
+    let mut options = Options::default();
+    let mut inputs = Vec::new();
+    let mut format_flag = false;
+
+    while let Some(arg) = args.next() {
+        format_flag |= FORMAT_FLAGS.contains(&arg.as_str());
+        match arg.as_str() {
+            "--json-input" => options.json_input = true,
+            "--tsv-input" => options.tsv_input = true,
+            "--show-plus" => options.show_plus = true,
+            "--show-delta" => options.show_delta = true,
+            "--describe" => options.describe = true,
+            "--row-all" => options.format = OutputFormat::RowAll,
+            "--auto" => options.auto = true,
+            "--guess" => options.guess = true,
+            "--dedup" => options.dedup = true,
+            "--dump" => options.dump = true,
+            "--validate" => options.validate = true,
+            "--compact" => options.compact = true,
+            "--count-only" => options.count_only = true,
+            "--with-formula" => options.with_formula = true,
+            "--both" => options.both = true,
+            "--numbers-only" => options.format = OutputFormat::Numbers,
+            "--logfmt" => options.format = OutputFormat::Logfmt,
+            "--columns" => options.format = OutputFormat::Columns,
+            "--minimal" => options.format = OutputFormat::Minimal,
+            "--delimiter" => options.delimiter = Some(args.next().ok_or("--delimiter needs a separator")?),
+            "--water-points" => {
+                let scale = args.next().ok_or("--water-points needs a scale")?;
+                options.water_points = Some(parse_scale_arg(&scale)?);
+            }
+            "--sort" => {
+                let order = args.next().ok_or("--sort needs asc or desc")?;
+                options.sort = Some(SortOrder::from_str(&order)?);
+            }
+            "--progress" => {
+                let interval = args.next().ok_or("--progress needs an interval")?;
+                options.progress = match usize::from_str(&interval) {
+                    Ok(interval) if interval > 0 => Some(interval),
+                    _ => return Err(format!("invalid progress interval {}", interval))
+                };
+            }
+            "--limit" => {
+                let limit = args.next().ok_or("--limit needs a count")?;
+                options.limit = Some(usize::from_str(&limit).map_err(|_| format!("invalid limit {}", limit))?);
+            }
+            // explicit spelling of a positional input; may be repeated
+            "--convert" => inputs.push(args.next().ok_or("--convert needs a temperature, e.g. 100CF")?),
+            "--output" => options.output = Some(args.next().ok_or("--output needs a file path")?),
+            "--color" => {
+                let choice = args.next().ok_or("--color needs auto, always or never")?;
+                options.color = ColorChoice::from_str(&choice)?;
+            }
+            "--to" => {
+                let scale = args.next().ok_or("--to needs a scale")?;
+                options.to = Some(parse_scale_arg(&scale)?);
+            }
+            "--precision" => {
+                let precision = args.next().ok_or("--precision needs a number of decimals")?;
+                options.precision = Some(parse_precision(&precision)?);
+            }
+            "--match-precision" => options.match_precision = true,
+            "--format" => {
+                let format = args.next().ok_or("--format needs arrow, row-all, numbers, logfmt, columns or minimal")?;
+                options.format = OutputFormat::from_str(&format)?;
+            }
+            "--default-target" => {
+                let scale = args.next().ok_or("--default-target needs a scale")?;
+                options.default_target = Some(parse_scale_arg(&scale)?);
+            }
+            "--from-stdin-scale" => {
+                let scale = args.next().ok_or("--from-stdin-scale needs a scale")?;
+                options.log_scale = Some(parse_scale_arg(&scale)?);
+            }
+            "--locale" => {
+                let locale = args.next().ok_or("--locale needs a value")?;
+                options.decimal_separator = DecimalSeparator::from_str(&locale)?;
+            }
+            flag if flag.starts_with("--") => return Err(format!("unknown option {}", flag)),
+            _ => inputs.push(arg)
+        }
+    }
+
+    apply_env_defaults(&mut options, format_flag)?;
+
+    if options.auto && options.to.is_some() {
+        return Err("--auto and --to cannot be combined".to_owned());
+    }
+    if options.default_target.is_some() && (options.auto || options.to.is_some()) {
+        return Err("--default-target cannot be combined with --auto or --to".to_owned());
+    }
+
+    Ok((options, inputs))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Command {
+    Convert,
+    Table,
+    Validate,
+    Interactive,
+}
+
+impl Command {
+    fn from_arg(arg: &str) -> Option<Command> {
+        match arg {
+            "convert" => Some(Command::Convert),
+            "table" => Some(Command::Table),
+            "validate" => Some(Command::Validate),
+            "interactive" => Some(Command::Interactive),
+            _ => None
+        }
+    }
+}
+
+// A known subcommand as the first argument selects it. Anything else, such as `100CF`, is the
+// bare form of `convert`.
+fn split_command(mut args: Vec<String>) -> (Command, Vec<String>) {
+    match args.first().and_then(|arg| Command::from_arg(arg)) {
+        Some(command) => {
+            args.remove(0);
+            (command, args)
+        }
+        None => (Command::Convert, args)
+    }
+}
+
+fn main() {
+    let (command, args) = split_command(std::env::args().skip(1).collect());
+    let (mut options, app_args) = match parse_args(args.into_iter()) {
+        Ok(parsed) => parsed,
+        Err(err) => {
+            eprintln!("{}", err);
+            std::process::exit(1);
+        }
+    };
+
+    if options.color == ColorChoice::Auto {
+        let terminal = options.output.is_none() && std::io::stdout().is_terminal();
+        options.color = if terminal { ColorChoice::Always } else { ColorChoice::Never };
+    }
+
+    // open the destination first so a bad path fails before any work is done
+    let mut output: Box<dyn Write> = match &options.output {
+        Some(path) => match File::create(path) {
+            Ok(file) => Box::new(BufWriter::new(file)),
+            Err(err) => {
+                eprintln!("OutputError: cannot open {}, {}", path, err);
+                std::process::exit(1);
+            }
+        },
+        None => Box::new(std::io::stdout().lock())
+    };
+
+    // `--validate` is the flag spelling of the `validate` subcommand, and `--count-only` a mode of it
+    let command = if options.validate || options.count_only { Command::Validate } else { command };
+    let success = match command {
+        Command::Convert => run_convert(&options, app_args, &mut output),
+        Command::Table => run_table(&options, app_args, &mut output),
+        Command::Validate => run_validate(&options, app_args, &mut output),
+        Command::Interactive => run_interactive(&options, &mut output)
+    };
+
+    flush_output(&mut output);
+    if !success {
+        std::process::exit(1);
     }
 }
 
-impl Display for Temperature {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}{} => {}{}", self.value, self.scale, self.convert(), self.convert_to)
+fn print_usage() {
+    eprintln!("Usage exemple: {} 32FC 45FK 36CK 32CF", get_exec_name());
+    eprintln!("Subcommands: convert (default), table CF [start end step], validate, interactive");
+}
+
+fn parse_input(elem: &str, options: &Options) -> Result<Temperature, ParseTemperatureError> {
+    reject_negative_kelvin(parse_unchecked_input(elem, options)?, options)
+}
+
+// Never print a negative Kelvin result: rejects sources below absolute zero when converting to
+// Kelvin, or with any target under `--row-all`, whose rows always include Kelvin. Every input
+// path goes through this before printing; `table` checks its start the same way.
+fn reject_negative_kelvin(temp: Temperature, options: &Options) -> Result<Temperature, ParseTemperatureError> {
+    if temp.convert_to() == Scale::Kelvin || options.format == OutputFormat::RowAll {
+        Temperature::new(temp.value(), temp.scale(), Scale::Kelvin).try_convert()?;
+    }
+
+    Ok(temp)
+}
+
+fn parse_unchecked_input(elem: &str, options: &Options) -> Result<Temperature, ParseTemperatureError> {
+    // `--guess` gives bare numbers such as `98` a source scale from guess_scale
+    if options.guess {
+        if let Some(temp) = Temperature::parse_guessed(elem)? {
+            let target = options.to.or(options.default_target).unwrap_or(temp.convert_to());
+            return Ok(Temperature::new(temp.value(), temp.scale(), target));
+        }
+    }
+
+    match (options.to, options.default_target) {
+        (Some(target), _) => Temperature::parse_with_target(elem, target),
+        _ if options.auto => Temperature::parse_with_auto_target(elem),
+        (None, Some(default)) => Temperature::parse_with_default_target(elem, default),
+        (None, None) => Temperature::from_str(elem)
     }
 }
 
-impl FromStr for Temperature {
-    type Err = ParseTemperatureError;
+fn run_convert(options: &Options, app_args: Vec<String>, output: &mut impl Write) -> bool {
+    if let Some(scale) = options.water_points {
+        let separator = options.decimal_separator;
+        write_line(output, &apply_separator(format!("freezing: {}{}", water_freezing_point(scale), scale), separator));
+        write_line(output, &apply_separator(format!("boiling: {}{}", water_boiling_point(scale), scale), separator));
+
+        if app_args.is_empty() && !options.json_input && !options.tsv_input {
+            return true;
+        }
+    }
+
+    if let Some(scale) = options.log_scale {
+        convert_log_lines(scale, options, output);
+        return true;
+    }
 
-    fn from_str(temp: &str) -> Result<Self, Self::Err> {
-        let temp = temp.trim();
-        if temp.is_empty() {
-            return Err(ParseTemperatureError { kind: TemperatureErrorKind::Inconvertible });
+    let mut temperature_list: Vec<Temperature> = Vec::new();
+    if options.json_input {
+        read_json_input(&mut temperature_list, options);
+    } else if options.tsv_input {
+        read_tsv_input(&mut temperature_list, options);
+    } else {
+        if app_args.is_empty() {
+            print_usage();
+            std::process::exit(1);
         }
-        if temp.len() == 1 {
-            return match f64::from_str(temp) {
-                Ok(_) => Err(ParseTemperatureError { kind: TemperatureErrorKind::ScaleUnknown }),
-                _ => Err(ParseTemperatureError { kind: TemperatureErrorKind::NotNumeric })
+
+        let mut progress = progress_for(options);
+
+        let mut inputs = read_stdin_args(app_args);
+        // stop before pulling more input once enough conversions succeeded
+        while below_limit(&temperature_list, options.limit) {
+            let Some(elem) = inputs.next() else {
+                break;
             };
+
+            if let Some(progress) = progress.as_mut() {
+                progress.tick();
+            }
+
+            match parse_input(&elem, options) {
+                Ok(temp) => temperature_list.push(temp),
+                Err(err) => eprintln!("ParseError: {}, {}", elem, err)
+            }
+        }
+    }
+
+    if let Some(order) = options.sort {
+        temperature_list.sort_by(|a, b| a.normalize().value.total_cmp(&b.normalize().value));
+
+        if order == SortOrder::Descending {
+            temperature_list.reverse();
         }
+    }
 
+    if options.dump {
+        for temp in temperature_list {
+            let line = format!("value={} from={} to={}", temp.value(), temp.scale(), temp.convert_to());
+            write_line(output, &apply_separator(line, options.decimal_separator));
+        }
+    } else if options.dedup {
+        for line in dedup_results(&temperature_list, options) {
+            write_line(output, &line);
+        }
+    } else {
+        write_temperatures(output, &temperature_list, options);
+    }
 
-        let scales_index = temp.len() - 2;
-        let temp = temp.to_uppercase();
-        let (value, scales) = temp.split_at(scales_index);
+    true
+}
 
-        let scales = match scales {
-            "CF" => (Scale::Celsius, Scale::Fahrenheit),
-            "CK" => (Scale::Celsius, Scale::Kelvin),
-            "FC" => (Scale::Fahrenheit, Scale::Celsius),
-            "FK" => (Scale::Fahrenheit, Scale::Kelvin),
-            "KC" => (Scale::Kelvin, Scale::Celsius),
-            "KF" => (Scale::Kelvin, Scale::Fahrenheit),
-            _ => return Err(ParseTemperatureError { kind: TemperatureErrorKind::ScaleUnknown })
-        };
+const MAX_TABLE_ROWS: usize = 100_000;
 
-        let value = match f64::from_str(value) {
-            Ok(value_parsed) => value_parsed,
-            _ => return Err(ParseTemperatureError { kind: TemperatureErrorKind::NotNumeric })
-        };
+// `table CF [start end step]` prints one conversion per step, -40 to 100 by 10 by default.
+fn run_table(options: &Options, app_args: Vec<String>, output: &mut impl Write) -> bool {
+    let Some(code) = app_args.first() else {
+        print_usage();
+        return false;
+    };
 
-        Ok(Temperature { value, scale: scales.0, convert_to: scales.1 })
+    let (from, to) = match parse_scale_pair(code) {
+        Ok(scales) => scales,
+        Err(err) => {
+            eprintln!("ParseError: {}, {}", code, err);
+            return false;
+        }
+    };
+
+    let bounds: Result<Vec<f64>, _> = app_args[1..].iter().map(|arg| f64::from_str(arg)).collect();
+    let (start, end, step) = match bounds.as_deref() {
+        Ok([]) => (-40.0, 100.0, 10.0),
+        Ok(&[start, end, step]) if step > 0.0 && start <= end && [start, end, step].iter().all(|bound| bound.is_finite()) => {
+            (start, end, step)
+        }
+        _ => {
+            eprintln!("table expects a finite start, end and positive step, e.g. table CF -40 100 10");
+            return false;
+        }
+    };
+
+    if (to == Scale::Kelvin || options.format == OutputFormat::RowAll) && start < from.absolute_zero() {
+        eprintln!("table start {}{} is below absolute zero", start, from);
+        return false;
+    }
+
+    // counted in floating point first, so a huge range cannot overflow the row count
+    let rows = ((end - start) / step + 1e-9).floor() + 1.0;
+    if rows > MAX_TABLE_ROWS as f64 {
+        eprintln!("table would have {} rows, more than the {} allowed", rows, MAX_TABLE_ROWS);
+        return false;
     }
+
+    let rows = rows as usize;
+    let temperature_list: Vec<Temperature> = (0..rows)
+        .map(|row| Temperature::new(start + step * row as f64, from, to))
+        .collect();
+    write_temperatures(output, &temperature_list, options);
+
+    true
 }
 
-fn main() {
-    let app_args = std::env::args().skip(1);
+// Reports whether each input parses and converts to a physically valid result. `--compact`
+// prints one `.` or `F` per input on a single line, then a summary; `--count-only` just the totals.
+fn run_validate(options: &Options, app_args: Vec<String>, output: &mut impl Write) -> bool {
+    if app_args.is_empty() {
+        print_usage();
+        return false;
+    }
+
+    let mut statuses = String::new();
+    let (mut passed, mut failed) = (0, 0);
+    for elem in read_stdin_args(app_args) {
+        let result = parse_input(&elem, options).and_then(|temp| temp.try_convert());
+        if result.is_ok() {
+            passed += 1;
+        } else {
+            failed += 1;
+        }
+
+        match (options.count_only, options.compact, result) {
+            (true, _, _) => {}
+            (false, true, Ok(_)) => statuses.push('.'),
+            (false, true, Err(_)) => statuses.push('F'),
+            (false, false, Ok(_)) => write_line(output, &format!("{}: ok", elem)),
+            (false, false, Err(err)) => write_line(output, &format!("{}: {}", elem, err))
+        }
+    }
+
+    if options.count_only {
+        write_line(output, &format!("valid: {}, invalid: {}", passed, failed));
+    } else if options.compact {
+        write_line(output, &statuses);
+        write_line(output, &format!("{} passed, {} failed", passed, failed));
+    }
+
+    failed == 0
+}
+
+// Converts stdin line by line as it is typed, until EOF or `quit`. The prompt goes to stderr
+// so it stays out of the results, including when they are written to `--output`.
+fn run_interactive(options: &Options, output: &mut impl Write) -> bool {
+    let prompt = std::io::stdin().is_terminal();
+
+    loop {
+        if prompt {
+            eprint!("> ");
+        }
+
+        let mut line = String::new();
+        match std::io::stdin().read_line(&mut line) {
+            Ok(0) | Err(_) => break,
+            Ok(_) => {}
+        }
+
+        let line = line.trim();
+        match line {
+            "" => continue,
+            "quit" | "exit" => break,
+            _ => match parse_input(line, options) {
+                Ok(temp) => write_line(output, &format_temperature(&temp, options)),
+                Err(err) => eprintln!("ParseError: {}, {}", line, err)
+            }
+        }
+
+        flush_output(output);
+    }
+
+    true
+}
 
-    if app_args.len() <= 0 {
-        eprintln!("Usage exemple: {} 32FC 45FK 36CK 32CF", get_exec_name());
+fn flush_output(output: &mut impl Write) {
+    if let Err(err) = output.flush() {
+        eprintln!("OutputError: {}", err);
         std::process::exit(1);
     }
+}
 
-    let mut temperature_list: Vec<Temperature> = Vec::new();
-    for elem in app_args {
-        match Temperature::from_str(&elem) {
-            Ok(temp) => temperature_list.push(temp),
-            Err(err) => println!("ParseError: {}, {}", elem, err)
+fn write_line(output: &mut impl Write, line: &str) {
+    if let Err(err) = writeln!(output, "{}", line) {
+        eprintln!("OutputError: {}", err);
+        std::process::exit(1);
+    }
+}
+
+fn below_limit(temperature_list: &[Temperature], limit: Option<usize>) -> bool {
+    limit.is_none_or(|limit| temperature_list.len() < limit)
+}
+
+// Expands each `-` argument into the non-blank lines of stdin, read lazily.
+fn read_stdin_args(args: Vec<String>) -> impl Iterator<Item = String> {
+    args.into_iter().flat_map(|arg| -> Box<dyn Iterator<Item = String>> {
+        if arg == "-" {
+            Box::new(std::io::stdin().lines().map_while(Result::ok).filter(|line| !line.trim().is_empty()))
+        } else {
+            Box::new(std::iter::once(arg))
+        }
+    })
+}
+
+// `--match-precision` uses the decimals of the input where known, otherwise `--precision` applies.
+fn precision_for(temp: &Temperature, options: &Options) -> Option<usize> {
+    match options.match_precision {
+        true => temp.input_decimals().or(options.precision),
+        false => options.precision
+    }
+}
+
+fn format_temperature(temp: &Temperature, options: &Options) -> String {
+    let mut line = match options.format {
+        OutputFormat::Arrow => temp.format_with(result_value(temp, options), options.show_plus, precision_for(temp, options)),
+        OutputFormat::RowAll => format_row_all(temp),
+        OutputFormat::Numbers => format_result(temp, options),
+        // stable between runs for diffing: no arrow and no floating point noise
+        OutputFormat::Minimal => {
+            format!("{}{} {}{}", temp.value(), temp.scale(), rounded_result(temp), temp.convert_to())
+        }
+        OutputFormat::Columns => column_fields(temp, options).join(options.delimiter.as_deref().unwrap_or(" ")),
+        OutputFormat::Logfmt => {
+            let (from, to) = (temp.scale(), temp.convert_to());
+            format!("input={}{} from={} to={} result={}", temp.value(), from, from.name(), to.name(),
+                    format_result(temp, options))
         }
+    };
+
+    // plain difference of the two numbers; across scales it is not a physical temperature change
+    if options.show_delta {
+        line.push_str(&format!(" (Δ{})", temp.unchecked_convert() - temp.value() + 0.0));
+    }
+
+    if options.describe {
+        line.push_str(&format!(" ({})", temp.describe()));
+    }
+
+    if options.both {
+        line.push_str(&format!(" (exact: {})", temp.unchecked_convert() + 0.0));
+    }
+
+    if options.with_formula {
+        line.push_str(&format!("  ({})", conversion_formula(temp.scale(), temp.convert_to())));
+    }
+
+    line = apply_separator(line, options.decimal_separator);
+
+    let celsius = Temperature::new(temp.value(), temp.scale(), Scale::Celsius).unchecked_convert();
+    if let (ColorChoice::Always, Some(color)) = (options.color, color_for(celsius)) {
+        line = format!("{}{}\x1b[0m", color, line);
+    }
+
+    line
+}
+
+// The converted number to print. `--both` shows the cleaned value up front and the exact one
+// in its annotation.
+fn result_value(temp: &Temperature, options: &Options) -> f64 {
+    match options.both {
+        true => rounded_result(temp),
+        false => temp.unchecked_convert() + 0.0
     }
+}
+
+// The bare converted number, honouring `--show-plus` and the precision options.
+fn format_result(temp: &Temperature, options: &Options) -> String {
+    format_number(result_value(temp, options), options.show_plus, precision_for(temp, options))
+}
+
+// Writes one formatted line per temperature; `--columns` output is aligned across all of them.
+fn write_temperatures(output: &mut impl Write, temperature_list: &[Temperature], options: &Options) {
+    if options.format == OutputFormat::Columns {
+        for line in format_columns(temperature_list, options) {
+            write_line(output, &line);
+        }
+    } else {
+        for temp in temperature_list {
+            write_line(output, &format_temperature(temp, options));
+        }
+    }
+}
+
+// Value, source unit, target unit and result, as separate fields.
+fn column_fields(temp: &Temperature, options: &Options) -> [String; 4] {
+    [temp.value().to_string(), temp.scale().to_string(), temp.convert_to().to_string(), format_result(temp, options)]
+}
+
+// Pads the `--columns` fields to the widest entry of each column, numbers to the right.
+// The annotations and colors of the other formats are left out so the columns stay plain.
+fn format_columns(temperature_list: &[Temperature], options: &Options) -> Vec<String> {
+    let rows: Vec<[String; 4]> = temperature_list.iter().map(|temp| column_fields(temp, options)).collect();
+    let mut widths = [0; 4];
+    for row in &rows {
+        for (width, field) in widths.iter_mut().zip(row) {
+            *width = (*width).max(field.chars().count());
+        }
+    }
+
+    let delimiter = options.delimiter.as_deref().unwrap_or(" ");
+    rows.iter()
+        .map(|[value, from, to, result]| {
+            let line = [
+                format!("{:>width$}", value, width = widths[0]),
+                format!("{:<width$}", from, width = widths[1]),
+                format!("{:<width$}", to, width = widths[2]),
+                format!("{:>width$}", result, width = widths[3]),
+            ].join(delimiter);
+
+            apply_separator(line, options.decimal_separator)
+        })
+        .collect()
+}
+
+// Writes the decimal points of a formatted line with the locale's separator.
+fn apply_separator(line: String, separator: DecimalSeparator) -> String {
+    match separator {
+        DecimalSeparator::Comma => line.replace('.', ","),
+        DecimalSeparator::Point => line
+    }
+}
+
+// The converted value rounded to two decimals.
+fn rounded_result(temp: &Temperature) -> f64 {
+    (temp.unchecked_convert() * 100.0).round() / 100.0 + 0.0
+}
+
+// Collapses results equal to two decimals into `212F (×3)` lines, in order of first appearance.
+fn dedup_results(temperature_list: &[Temperature], options: &Options) -> Vec<String> {
+    let mut counted: Vec<(f64, Scale, usize)> = Vec::new();
 
     for temp in temperature_list {
-        println!("{}", temp)
+        let result = rounded_result(temp);
+
+        match counted.iter_mut().find(|(value, scale, _)| *value == result && *scale == temp.convert_to()) {
+            Some((_, _, count)) => *count += 1,
+            None => counted.push((result, temp.convert_to(), 1))
+        }
     }
+
+    counted.into_iter()
+        .map(|(value, scale, count)| format!("{}{} (×{})", value, scale, count))
+        .map(|line| apply_separator(line, options.decimal_separator))
+        .collect()
+}
+
+fn format_row_all(temp: &Temperature) -> String {
+    let others = Scale::ALL.into_iter().filter(|scale| *scale != temp.scale());
+    let mut row = vec![format!("{}{}", temp.value(), temp.scale())];
+
+    for scale in others {
+        let value = Temperature::new(temp.value(), temp.scale(), scale).unchecked_convert() + 0.0;
+        row.push(format!("{}{}", value, scale));
+    }
+
+    row.join(" = ")
+}
+
+// Reads sensor log lines such as `2024-01-01T00:00 37.5` from stdin, taking the last field as
+// a value in `scale`, and reprints each line with the converted value appended. Lines whose
+// last field is not a number are kept as they are.
+fn convert_log_lines(scale: Scale, options: &Options, output: &mut impl Write) {
+    let target = options.to.unwrap_or(scale.auto_target());
+
+    for (index, line) in std::io::stdin().lines().map_while(Result::ok).enumerate() {
+        let value = line.split_whitespace().last().map(f64::from_str);
+
+        match value {
+            Some(Ok(value)) => match reject_negative_kelvin(Temperature::new(value, scale, target), options) {
+                Ok(temp) => {
+                    // only the appended result is localized; the log line is echoed as read
+                    let result = apply_separator(format_result(&temp, options), options.decimal_separator);
+                    write_line(output, &format!("{} {}{}", line, result, target));
+                }
+                Err(err) => {
+                    eprintln!("ParseError: line {}, {}", index + 1, err);
+                    write_line(output, &line);
+                }
+            },
+            _ => {
+                if !line.trim().is_empty() {
+                    eprintln!("ParseError: line {}, no trailing value", index + 1);
+                }
+                write_line(output, &line);
+            }
+        }
+    }
+}
+
+// Reads `value<TAB>from<TAB>to` rows from stdin, stopping once `--limit` of them converted.
+fn read_tsv_input(temperature_list: &mut Vec<Temperature>, options: &Options) {
+    let mut lines = std::io::stdin().lines().map_while(Result::ok).enumerate();
+    let mut progress = progress_for(options);
+
+    while below_limit(temperature_list, options.limit) {
+        let Some((index, line)) = lines.next() else {
+            break;
+        };
+
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        if let Some(progress) = progress.as_mut() {
+            progress.tick();
+        }
+
+        let columns: Vec<&str> = line.split('\t').map(str::trim).collect();
+        let [value, from, to] = columns[..] else {
+            eprintln!("ParseError: line {}, expected 3 tab-separated columns", index + 1);
+            continue;
+        };
+
+        let parsed = Scale::from_str(to)
+            .and_then(|to| Temperature::parse_with_target(&format!("{}{}", value, from), to))
+            .and_then(|temp| reject_negative_kelvin(temp, options));
+
+        match parsed {
+            Ok(temp) => temperature_list.push(temp),
+            Err(err) => eprintln!("ParseError: line {}, {}", index + 1, err)
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct JsonInput {
+    value: f64,
+    from: String,
+    to: String,
+}
+
+// Reads a JSON array of inputs from stdin element by element, so that reading stops once
+// `--limit` of them converted.
+#[cfg(feature = "serde")]
+fn read_json_input(temperature_list: &mut Vec<Temperature>, options: &Options) {
+    let mut deserializer = serde_json::Deserializer::from_reader(std::io::stdin().lock());
+    let visitor = JsonInputVisitor {
+        temperature_list: &mut *temperature_list,
+        options,
+        progress: progress_for(options),
+    };
+
+    // stopping at the limit leaves the rest of the array unread, which serde_json reports
+    if let Err(err) = serde::Deserializer::deserialize_seq(&mut deserializer, visitor) {
+        if below_limit(temperature_list, options.limit) {
+            eprintln!("JsonError: {}", err);
+            std::process::exit(1);
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+struct JsonInputVisitor<'a> {
+    temperature_list: &'a mut Vec<Temperature>,
+    options: &'a Options,
+    progress: Option<Progress<fn(usize)>>,
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::de::Visitor<'de> for JsonInputVisitor<'_> {
+    type Value = ();
+
+    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str("an array of {value, from, to} objects")
+    }
+
+    fn visit_seq<A: serde::de::SeqAccess<'de>>(mut self, mut seq: A) -> Result<(), A::Error> {
+        while below_limit(self.temperature_list, self.options.limit) {
+            let Some(input) = seq.next_element::<JsonInput>()? else {
+                break;
+            };
+
+            if let Some(progress) = self.progress.as_mut() {
+                progress.tick();
+            }
+
+            let parsed = Scale::from_str(&input.from)
+                .and_then(|from| Ok(Temperature::new(input.value, from, Scale::from_str(&input.to)?)))
+                .and_then(|temp| reject_negative_kelvin(temp, self.options));
+
+            match parsed {
+                Ok(temp) => self.temperature_list.push(temp),
+                Err(err) => eprintln!("ParseError: {}{}{}, {}", input.value, input.from, input.to, err)
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(not(feature = "serde"))]
+fn read_json_input(_temperature_list: &mut Vec<Temperature>, _options: &Options) {
+    eprintln!("--json-input requires the serde feature");
+    std::process::exit(1);
 }
 
 fn get_exec_name() -> String {
-    // let exec_cow = std::env::current_exe()
-    //     .ok()
-    //     .expect("error ...")
-    //     .file_name()
-    //     .expect("error ...")
-    //     .to_string_lossy()
-    //     .into_owned();
-
-    std::env::current_exe()
-        .unwrap()
-        .file_name()
-        .unwrap()
-        .to_str()
-        .unwrap()
+    exec_name_from(std::env::current_exe().ok())
+}
+
+// Falls back to the package name when the executable path is unknown or not valid UTF-8,
+// which happens in some sandboxes.
+fn exec_name_from(path: Option<PathBuf>) -> String {
+    path.as_deref()
+        .and_then(Path::file_name)
+        .and_then(OsStr::to_str)
+        .unwrap_or(env!("CARGO_PKG_NAME"))
         .to_owned()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn split_command_routes_subcommands() {
+        assert_eq!(split_command(args(&["table", "CF"])), (Command::Table, args(&["CF"])));
+        assert_eq!(split_command(args(&["validate", "100CF"])), (Command::Validate, args(&["100CF"])));
+        assert_eq!(split_command(args(&["interactive"])), (Command::Interactive, args(&[])));
+        assert_eq!(split_command(args(&["convert", "100CF"])), (Command::Convert, args(&["100CF"])));
+    }
+
+    #[test]
+    fn split_command_falls_back_to_bare_convert() {
+        assert_eq!(split_command(args(&["100CF", "table"])), (Command::Convert, args(&["100CF", "table"])));
+        assert_eq!(split_command(args(&["--precision", "2", "100CF"])), (Command::Convert, args(&["--precision", "2", "100CF"])));
+        assert_eq!(split_command(args(&[])), (Command::Convert, args(&[])));
+    }
+
+    #[test]
+    fn color_thresholds() {
+        assert_eq!(color_for(COLD_BELOW_C - 0.1), Some("\x1b[34m"));
+        assert_eq!(color_for(COLD_BELOW_C), None);
+        assert_eq!(color_for(HOT_FROM_C - 0.1), None);
+        assert_eq!(color_for(HOT_FROM_C), Some("\x1b[31m"));
+    }
+
+    #[test]
+    fn progress_reports_every_interval() {
+        let mut reported = Vec::new();
+        let mut progress = Progress::new(3, |count| reported.push(count));
+        for _ in 0..10 {
+            progress.tick();
+        }
+
+        assert_eq!(reported, [3, 6, 9]);
+    }
+
+    #[test]
+    fn exec_name_falls_back_to_package_name() {
+        assert_eq!(exec_name_from(None), env!("CARGO_PKG_NAME"));
+        assert_eq!(exec_name_from(Some(PathBuf::from("/"))), env!("CARGO_PKG_NAME"));
+    }
+
+    #[test]
+    fn exec_name_is_file_name() {
+        assert_eq!(exec_name_from(Some(PathBuf::from("/usr/bin/tempconv"))), "tempconv");
+    }
+}
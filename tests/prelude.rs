@@ -0,0 +1,13 @@
+use std::str::FromStr;
+
+use temperature_converter::prelude::*;
+
+#[test]
+fn prelude_covers_a_conversion() {
+    let temp = Temperature::from_str("100CF").unwrap();
+    assert_eq!(temp.try_convert().unwrap(), 212.0);
+
+    assert_eq!(parse_scale_pair("KC").unwrap(), (Scale::Kelvin, Scale::Celsius));
+    assert_eq!(make_converter(Scale::Celsius, Scale::Kelvin)(0.0), celsius_to_kelvin(0.0));
+    assert!(Temperature::from_str("100XY").is_err_and(|err: ParseTemperatureError| !err.to_string().is_empty()));
+}
@@ -0,0 +1,320 @@
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Output, Stdio};
+
+fn assert_snapshot(name: &str, args: &[&str]) {
+    assert_snapshot_with_stdin(name, args, "");
+}
+
+fn assert_snapshot_with_stdin(name: &str, args: &[&str], stdin: &str) {
+    assert_snapshot_with_env(name, args, stdin, &[]);
+}
+
+// Runs the binary with `args`, `stdin` and the `env` variables set on top of an environment
+// without any TEMPCONV_ settings.
+fn run(args: &[&str], stdin: &str, env: &[(&str, &str)]) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_temperature_converter"))
+        .args(args)
+        .env_remove("TEMPCONV_PRECISION")
+        .env_remove("TEMPCONV_FORMAT")
+        .envs(env.iter().copied())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("failed to run the binary");
+    child.stdin.take().expect("stdin is piped").write_all(stdin.as_bytes()).expect("failed to write stdin");
+
+    child.wait_with_output().expect("failed to run the binary")
+}
+
+// Compares the stdout of `run` with `tests/snapshots/<name>.txt`. Run with UPDATE_SNAPSHOTS=1
+// to rewrite the files from the current output instead.
+fn assert_snapshot_with_env(name: &str, args: &[&str], stdin: &str, env: &[(&str, &str)]) {
+    let output = run(args, stdin, env);
+    let actual = String::from_utf8(output.stdout).expect("output is not UTF-8");
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/snapshots").join(format!("{}.txt", name));
+
+    if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+        fs::write(&path, &actual).expect("failed to write the snapshot");
+        return;
+    }
+
+    let expected = fs::read_to_string(&path)
+        .unwrap_or_else(|_| panic!("missing {}, run with UPDATE_SNAPSHOTS=1 to create it", path.display()));
+    assert_eq!(actual, expected, "output differs from {}", path.display());
+}
+
+#[test]
+fn convert() {
+    assert_snapshot("convert", &["32FC", "45FK", "36CK", "32CF", "-40CF", "100.5±0.3CK", "5mKC"]);
+}
+
+#[test]
+fn row_all() {
+    assert_snapshot("row_all", &["--row-all", "100CF", "0CF", "0KC"]);
+}
+
+#[test]
+fn table() {
+    assert_snapshot("table", &["table", "CF"]);
+}
+
+#[test]
+fn validate_compact() {
+    assert_snapshot("validate_compact", &["validate", "--compact", "100CF", "xx", "-500CF", "1KC"]);
+}
+
+#[test]
+fn logfmt() {
+    assert_snapshot("logfmt", &["--logfmt", "100CF", "0KC"]);
+}
+
+#[test]
+fn match_precision() {
+    assert_snapshot("match_precision", &["--match-precision", "37.5CF", "37.500CF", "37CF"]);
+}
+
+#[test]
+fn columns() {
+    assert_snapshot("columns", &["--columns", "100CF", "-40CF", "1.5KC", "36.6CK"]);
+}
+
+#[test]
+fn minimal() {
+    assert_snapshot("minimal", &["--minimal", "100CF", "37CF", "45FK", "0KF"]);
+}
+
+#[test]
+fn both() {
+    assert_snapshot("both", &["--both", "100CF", "45FK", "0KF"]);
+}
+
+#[test]
+fn sensor_log() {
+    let log = "2024-01-01T00:00 37.5\n# no reading\n2024-01-01T00:01 38\n";
+    assert_snapshot_with_stdin("sensor_log", &["--from-stdin-scale", "C", "--to", "F"], log);
+}
+
+#[test]
+fn negative_kelvin() {
+    assert_snapshot("negative_kelvin", &["-500CK", "-459.67FK", "-300CK", "0KK"]);
+}
+
+#[test]
+fn byte_order_mark() {
+    assert_snapshot("byte_order_mark", &["\u{feff}100CF", "\u{200b}37CK"]);
+}
+
+#[test]
+fn negative_kelvin_tsv() {
+    assert_snapshot_with_stdin("negative_kelvin_tsv", &["--tsv-input"], "-500\tC\tK\n100\tC\tK\n");
+}
+
+#[test]
+fn negative_kelvin_sensor_log() {
+    let log = "2024-01-01T00:00 -500\n2024-01-01T00:01 25\n";
+    assert_snapshot_with_stdin("negative_kelvin_sensor_log", &["--from-stdin-scale", "C", "--to", "K"], log);
+}
+
+#[test]
+fn limit() {
+    assert_snapshot_with_stdin("limit", &["--limit", "2", "-"], "1CK\nxx\n2CK\n3CK\n");
+}
+
+#[test]
+fn limit_tsv() {
+    assert_snapshot_with_stdin("limit_tsv", &["--tsv-input", "--limit", "2"], "1\tC\tK\nbad\n2\tC\tK\n3\tC\tK\n");
+}
+
+#[test]
+fn env_precision() {
+    assert_snapshot_with_env("env_precision", &["36.6CF"], "", &[("TEMPCONV_PRECISION", "1")]);
+}
+
+#[test]
+fn env_format() {
+    assert_snapshot_with_env("env_format", &["100CF"], "", &[("TEMPCONV_FORMAT", "minimal")]);
+}
+
+#[test]
+fn flags_override_env() {
+    let env = [("TEMPCONV_PRECISION", "1"), ("TEMPCONV_FORMAT", "minimal")];
+    assert_snapshot_with_env("flags_override_env", &["--precision", "3", "--format", "arrow", "36.6CF"], "", &env);
+
+    let env = [("TEMPCONV_PRECISION", "two"), ("TEMPCONV_FORMAT", "fancy")];
+    assert_snapshot_with_env("flags_override_invalid_env", &["--precision", "3", "--logfmt", "36.6CF"], "", &env);
+}
+
+#[test]
+fn invalid_env() {
+    let output = run(&["100CF"], "", &[("TEMPCONV_PRECISION", "two")]);
+
+    assert!(!output.status.success());
+    assert!(output.stdout.is_empty());
+    assert_eq!(String::from_utf8_lossy(&output.stderr), "TEMPCONV_PRECISION: invalid precision two\n");
+
+    let output = run(&["100CF"], "", &[("TEMPCONV_FORMAT", "fancy")]);
+    assert!(!output.status.success());
+    assert!(output.stdout.is_empty());
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn json_input() {
+    let json = r#"[{"value":100,"from":"C","to":"F"},{"value":-40,"from":"F","to":"C"},{"value":1,"from":"X","to":"K"}]"#;
+    assert_snapshot_with_stdin("json_input", &["--json-input"], json);
+}
+
+#[test]
+fn show_plus() {
+    assert_snapshot("show_plus", &["--show-plus", "100CF", "-40CF", "0KC", "273.15KC"]);
+}
+
+#[test]
+fn locale_comma() {
+    assert_snapshot("locale_comma", &["--locale", "de", "99.5FC", "37.5CF"]);
+}
+
+#[test]
+fn sort_asc() {
+    assert_snapshot("sort_asc", &["--sort", "asc", "--precision", "2", "100CF", "0KC", "100FC", "300KF"]);
+}
+
+#[test]
+fn sort_desc() {
+    assert_snapshot("sort_desc", &["--sort", "desc", "--precision", "2", "100CF", "0KC", "100FC", "300KF"]);
+}
+
+#[test]
+fn numbers_only() {
+    assert_snapshot("numbers_only", &["--numbers-only", "100CF", "xx", "0KC", "36.6CK"]);
+}
+
+#[test]
+fn to_overrides_token_target() {
+    assert_snapshot("to_overrides_token_target", &["--to", "K", "100CF", "37C", "32FC"]);
+}
+
+#[test]
+fn tsv_input() {
+    let tsv = "100\tC\tF\n-40\tF\tC\nbad row\n\n300\tK\tX\n0\tK\tC\n";
+    assert_snapshot_with_stdin("tsv_input", &["--tsv-input"], tsv);
+
+    let stderr = String::from_utf8(run(&["--tsv-input"], tsv, &[]).stderr).expect("stderr is not UTF-8");
+    assert_eq!(stderr, "ParseError: line 3, expected 3 tab-separated columns\nParseError: line 5, \"scale unknown\"\n");
+}
+
+#[test]
+fn progress_tsv_input() {
+    let tsv = "100\tC\tF\n\n-40\tF\tC\n0\tK\tC\n300\tK\tF\n";
+    let stderr = String::from_utf8(run(&["--tsv-input", "--progress", "2"], tsv, &[]).stderr).expect("stderr is not UTF-8");
+    assert_eq!(stderr, "processed 2 inputs\nprocessed 4 inputs\n");
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn progress_json_input() {
+    let json = r#"[{"value":100,"from":"C","to":"F"},{"value":-40,"from":"F","to":"C"},{"value":0,"from":"K","to":"C"}]"#;
+    let stderr = String::from_utf8(run(&["--json-input", "--progress", "2"], json, &[]).stderr).expect("stderr is not UTF-8");
+    assert_eq!(stderr, "processed 2 inputs\n");
+}
+
+#[test]
+fn output_file() {
+    let path = std::env::temp_dir().join(format!("temperature_converter_output_{}.txt", std::process::id()));
+    let output = run(&["--output", path.to_str().expect("temp path is UTF-8"), "100CF", "0KC"], "", &[]);
+    let written = fs::read_to_string(&path).expect("the output file was not written");
+    fs::remove_file(&path).expect("failed to remove the output file");
+
+    assert!(output.status.success());
+    assert!(output.stdout.is_empty());
+    assert_eq!(written, "100C => 212F\n0K => -273.15C\n");
+}
+
+#[test]
+fn output_file_cannot_be_opened() {
+    let path = std::env::temp_dir().join("temperature_converter_missing_directory").join("results.txt");
+    let output = run(&["--output", path.to_str().expect("temp path is UTF-8"), "100CF"], "", &[]);
+
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).starts_with("OutputError: cannot open"));
+}
+
+#[test]
+fn show_delta() {
+    assert_snapshot("show_delta", &["--show-delta", "100CK", "-40CF", "212FC"]);
+}
+
+#[test]
+fn dedup() {
+    assert_snapshot("dedup", &["--dedup", "100CF", "100CF", "373.15KF", "0CF", "100.001CF"]);
+}
+
+#[test]
+fn repeated_convert() {
+    assert_snapshot("repeated_convert", &["--convert", "100CF", "--convert", "37CK", "0KC"]);
+}
+
+#[test]
+fn count_only() {
+    assert_snapshot("count_only", &["--count-only", "100CF", "xx", "-500CK", "37CK", "1e400CF"]);
+}
+
+#[test]
+fn default_target() {
+    assert_snapshot("default_target", &["--default-target", "K", "100CF", "37C", "32FC", "212F"]);
+}
+
+#[test]
+fn table_rejects_unbounded_ranges() {
+    for args in [["table", "CF", "0", "inf", "1"], ["table", "CF", "0", "1e30", "1"], ["table", "CF", "nan", "1", "1"]] {
+        let output = run(&args, "", &[]);
+
+        assert!(!output.status.success(), "{:?}", args);
+        assert!(output.stdout.is_empty(), "{:?}", args);
+    }
+}
+
+#[test]
+fn guess() {
+    assert_snapshot("guess", &["--guess", "98", "\u{feff}212", "300", "37CK"]);
+
+    let long = "9".repeat(100);
+    let output = run(&["--guess", &long], "", &[]);
+    assert!(output.stdout.is_empty());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("input longer than 64 bytes"));
+}
+
+#[test]
+fn row_all_below_absolute_zero() {
+    assert_snapshot("row_all_below_absolute_zero", &["--row-all", "-500CF", "-5KC", "-40CF"]);
+    assert_snapshot_with_stdin("row_all_below_absolute_zero_tsv", &["--row-all", "--tsv-input"], "-500\tC\tF\n-40\tC\tF\n");
+}
+
+#[test]
+fn both_matches_arrow_layout() {
+    assert_snapshot("both_matches_arrow_layout", &["--both", "--precision", "2", "5mKC", "37.5±0.2CF"]);
+}
+
+#[test]
+fn with_formula() {
+    assert_snapshot("with_formula", &["--with-formula", "100CF", "212FK", "0KC"]);
+}
+
+#[test]
+fn sensor_log_locale() {
+    let log = "2024-01-01T00:00 37.5\n";
+    assert_snapshot_with_stdin("sensor_log_locale", &["--locale", "de", "--from-stdin-scale", "C", "--to", "F"], log);
+}
+
+#[test]
+fn water_points_locale() {
+    assert_snapshot("water_points_locale", &["--locale", "de", "--water-points", "K"]);
+}
+
+#[test]
+fn dump_locale() {
+    assert_snapshot("dump_locale", &["--locale", "de", "--dump", "37.5CF", "5mKC"]);
+}